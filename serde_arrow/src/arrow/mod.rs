@@ -0,0 +1,70 @@
+//! Conversion between `Vec<T>` and Arrow's `RecordBatch`
+//!
+use std::sync::Arc;
+
+use arrow::datatypes::{Field, Schema as ArrowSchema};
+use arrow::record_batch::RecordBatch;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    fail,
+    schema::{arrow_support, value},
+    Result, Schema,
+};
+
+/// Build a `RecordBatch` from a sequence of records, using `schema` to
+/// determine the Arrow type and nullability of each column
+///
+pub fn to_record_batch<T: Serialize + ?Sized>(items: &T, schema: &Schema) -> Result<RecordBatch> {
+    let rows = value::capture_rows(items)?;
+
+    let mut fields = Vec::new();
+    let mut columns = Vec::new();
+
+    for name in schema.fields() {
+        let data_type = match schema.data_type(name) {
+            Some(data_type) => data_type,
+            None => fail!("Missing data type for field {}", name),
+        };
+        let nullable = schema.is_nullable(name);
+
+        let values: Vec<value::Value> = rows.iter().map(|row| row.field(name)).collect();
+        let array = arrow_support::build_array(data_type, &values)?;
+
+        fields.push(Field::new(
+            name,
+            arrow::datatypes::DataType::try_from(data_type)?,
+            nullable,
+        ));
+        columns.push(array);
+    }
+
+    let arrow_schema = Arc::new(ArrowSchema::new(fields));
+    RecordBatch::try_new(arrow_schema, columns).map_err(|err| format!("{err}").into())
+}
+
+/// Read a `RecordBatch` back into a `Vec<T>`, using `schema` to determine how
+/// each column should be interpreted
+///
+pub fn from_record_batch<T>(batch: &RecordBatch, schema: &Schema) -> Result<Vec<T>>
+where
+    T: for<'de> Deserialize<'de>,
+{
+    let mut rows: Vec<Vec<(String, value::Value)>> = vec![Vec::new(); batch.num_rows()];
+
+    for (idx, name) in schema.fields().iter().enumerate() {
+        let data_type = match schema.data_type(name) {
+            Some(data_type) => data_type,
+            None => fail!("Missing data type for field {}", name),
+        };
+        let values = arrow_support::extract_values(batch.column(idx).as_ref(), data_type)?;
+
+        for (row, value) in rows.iter_mut().zip(values) {
+            row.push((name.clone(), value));
+        }
+    }
+
+    rows.into_iter()
+        .map(|row| value::from_value(value::Value::Struct(row)))
+        .collect()
+}
@@ -0,0 +1,38 @@
+use arrow2::{array::Array, chunk::Chunk};
+use serde::Deserialize;
+
+use crate::{
+    fail,
+    schema::{arrow2_support, value},
+    Result, Schema,
+};
+
+/// Read an arrow2 [Chunk] back into a `Vec<T>`, using `schema` to determine
+/// how each column should be interpreted
+pub fn from_chunk<T>(chunk: &Chunk<Box<dyn Array>>, schema: &Schema) -> Result<Vec<T>>
+where
+    T: for<'de> Deserialize<'de>,
+{
+    let num_rows = chunk.arrays().first().map(|array| array.len()).unwrap_or(0);
+    let mut rows: Vec<Vec<(String, value::Value)>> = vec![Vec::new(); num_rows];
+
+    for (idx, name) in schema.fields().iter().enumerate() {
+        let data_type = match schema.data_type(name) {
+            Some(data_type) => data_type,
+            None => fail!("Missing data type for field {}", name),
+        };
+        let array = match chunk.arrays().get(idx) {
+            Some(array) => array.as_ref(),
+            None => fail!("Chunk is missing a column for field {}", name),
+        };
+        let values = arrow2_support::extract_values(array, data_type)?;
+
+        for (row, value) in rows.iter_mut().zip(values) {
+            row.push((name.clone(), value));
+        }
+    }
+
+    rows.into_iter()
+        .map(|row| value::from_value(value::Value::Struct(row)))
+        .collect()
+}
@@ -1,11 +1,15 @@
 mod from_chunk;
 mod to_chunk;
 
+#[cfg(feature = "arrow2-io_ipc")]
+mod read_ipc;
 #[cfg(feature = "arrow2-io_ipc")]
 mod write_ipc;
 
 pub use from_chunk::from_chunk;
 pub use to_chunk::to_chunk;
 
+#[cfg(feature = "arrow2-io_ipc")]
+pub use read_ipc::{read_ipc, read_ipc_stream};
 #[cfg(feature = "arrow2-io_ipc")]
 pub use write_ipc::write_ipc;
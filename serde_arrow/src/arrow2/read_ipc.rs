@@ -0,0 +1,68 @@
+use std::io::{Read, Seek};
+
+use arrow2::io::ipc::read::{
+    read_file_metadata, read_stream_metadata, FileReader, StreamReader, StreamState,
+};
+use serde::Deserialize;
+
+use crate::{Result, Schema};
+
+use super::from_chunk::from_chunk;
+
+/// Read an arrow2 IPC file into a `Vec<T>`
+///
+/// This is the inverse of [super::write_ipc]. The reader must support seeking,
+/// since the file format stores its footer (schema and batch offsets) at the
+/// end of the stream. For a sequential, non-seekable source use
+/// [read_ipc_stream] instead.
+///
+/// Records are deserialized batch by batch and concatenated, so the file may
+/// contain any number of record batches, each with a different number of
+/// rows.
+///
+pub fn read_ipc<T, R>(mut reader: R) -> Result<Vec<T>>
+where
+    T: for<'de> Deserialize<'de>,
+    R: Read + Seek,
+{
+    let metadata = read_file_metadata(&mut reader)?;
+    let schema = Schema::try_from(&metadata.schema)?;
+    let file_reader = FileReader::new(reader, metadata, None, None);
+
+    let mut items = Vec::new();
+    for chunk in file_reader {
+        let chunk = chunk?;
+        items.extend(from_chunk::<T>(&chunk, &schema)?);
+    }
+
+    Ok(items)
+}
+
+/// Read an arrow2 IPC stream into a `Vec<T>`
+///
+/// Unlike [read_ipc], this function only requires [Read] and can therefore be
+/// used with non-seekable sources such as a network socket.
+///
+pub fn read_ipc_stream<T, R>(mut reader: R) -> Result<Vec<T>>
+where
+    T: for<'de> Deserialize<'de>,
+    R: Read,
+{
+    let metadata = read_stream_metadata(&mut reader)?;
+    let schema = Schema::try_from(&metadata.schema)?;
+    let stream_reader = StreamReader::new(reader, metadata, None);
+
+    let mut items = Vec::new();
+    for message in stream_reader {
+        // `StreamState::Waiting` means the reader needs more bytes before it
+        // can produce the next chunk; since `reader` is a plain `Read` (no
+        // async polling loop here), it only ever occurs at the very end of
+        // the stream, where no more bytes will arrive, so reading stops.
+        match message? {
+            StreamState::Some(chunk) => items.extend(from_chunk::<T>(&chunk, &schema)?),
+            StreamState::Waiting => break,
+        }
+    }
+
+    Ok(items)
+}
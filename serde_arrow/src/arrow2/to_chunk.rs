@@ -0,0 +1,50 @@
+use arrow2::{array::Array, chunk::Chunk, datatypes::Field};
+use serde::Serialize;
+
+use crate::{
+    fail,
+    schema::{arrow2_support, value},
+    Result, Schema,
+};
+
+/// Build an arrow2 [Chunk] from a sequence of records, using `schema` to
+/// determine the Arrow2 type and nullability of each column
+pub fn to_chunk<T: Serialize + ?Sized>(
+    items: &T,
+    schema: &Schema,
+) -> Result<Chunk<Box<dyn Array>>> {
+    let rows = value::capture_rows(items)?;
+
+    let mut columns = Vec::new();
+    for name in schema.fields() {
+        let data_type = match schema.data_type(name) {
+            Some(data_type) => data_type,
+            None => fail!("Missing data type for field {}", name),
+        };
+
+        let values: Vec<value::Value> = rows.iter().map(|row| row.field(name)).collect();
+        columns.push(arrow2_support::build_array(data_type, &values)?);
+    }
+
+    Chunk::try_new(columns).map_err(|err| format!("{err}").into())
+}
+
+/// Derive the arrow2 fields for `schema`, in the order returned by
+/// [Schema::fields]
+pub(crate) fn to_arrow2_fields(schema: &Schema) -> Result<Vec<Field>> {
+    schema
+        .fields()
+        .iter()
+        .map(|name| {
+            let data_type = match schema.data_type(name) {
+                Some(data_type) => data_type,
+                None => fail!("Missing data type for field {}", name),
+            };
+            Ok(Field::new(
+                name,
+                arrow2::datatypes::DataType::try_from(data_type)?,
+                schema.is_nullable(name),
+            ))
+        })
+        .collect()
+}
@@ -0,0 +1,33 @@
+use std::io::Write;
+
+use arrow2::io::ipc::write::{FileWriter, WriteOptions};
+use serde::Serialize;
+
+use crate::{Result, Schema};
+
+use super::to_chunk::{to_arrow2_fields, to_chunk};
+
+/// Write a sequence of records to `writer` as an arrow2 IPC file
+///
+/// This is the inverse of [super::read_ipc].
+pub fn write_ipc<T, W>(items: &T, schema: &Schema, writer: W) -> Result<()>
+where
+    T: Serialize + ?Sized,
+    W: Write,
+{
+    let arrow2_schema = arrow2::datatypes::Schema::from(to_arrow2_fields(schema)?);
+    let chunk = to_chunk(items, schema)?;
+
+    let mut file_writer = FileWriter::try_new(
+        writer,
+        arrow2_schema,
+        None,
+        WriteOptions { compression: None },
+    )
+    .map_err(|err| format!("{err}"))?;
+
+    file_writer.write(&chunk, None).map_err(|err| format!("{err}"))?;
+    file_writer.finish().map_err(|err| format!("{err}"))?;
+
+    Ok(())
+}
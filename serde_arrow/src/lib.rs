@@ -35,9 +35,18 @@
 //!
 mod error;
 pub mod event;
+mod internal;
 mod ops;
 mod schema;
 
+/// Experimental, unstable configuration options
+///
+/// Anything exposed here may change or be removed without a major version
+/// bump.
+pub mod experimental {
+    pub use crate::internal::{configure, Configuration, DuplicateKeyPolicy};
+}
+
 #[cfg(feature = "arrow")]
 pub mod arrow;
 
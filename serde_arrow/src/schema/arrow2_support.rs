@@ -0,0 +1,402 @@
+//! Conversion between [DataType] and the `arrow2` crate's data types, and the
+//! column builders / extractors used by [crate::arrow2::to_chunk] /
+//! [crate::arrow2::from_chunk]
+//!
+use arrow2::array::{Array, BinaryArray, BooleanArray, PrimitiveArray, Utf8Array};
+use arrow2::datatypes::DataType as Arrow2Type;
+use arrow2::types::NativeType;
+
+use crate::{fail, Result};
+
+use super::{
+    datetime::{format_naive_datetime, millis_to_naive_datetime, parse_naive_datetime},
+    decimal::{i128_to_decimal_str, value_to_decimal_i128},
+    value::Value,
+    DataType,
+};
+
+impl TryFrom<&DataType> for Arrow2Type {
+    type Error = crate::Error;
+
+    fn try_from(data_type: &DataType) -> Result<Self> {
+        match data_type {
+            DataType::Bool => Ok(Arrow2Type::Boolean),
+            DataType::I8 => Ok(Arrow2Type::Int8),
+            DataType::I16 => Ok(Arrow2Type::Int16),
+            DataType::I32 => Ok(Arrow2Type::Int32),
+            DataType::I64 => Ok(Arrow2Type::Int64),
+            DataType::U8 => Ok(Arrow2Type::UInt8),
+            DataType::U16 => Ok(Arrow2Type::UInt16),
+            DataType::U32 => Ok(Arrow2Type::UInt32),
+            DataType::U64 => Ok(Arrow2Type::UInt64),
+            DataType::F32 => Ok(Arrow2Type::Float32),
+            DataType::F64 => Ok(Arrow2Type::Float64),
+            DataType::DateTimeStr
+            | DataType::NaiveDateTimeStr
+            | DataType::DateTimeMilliseconds
+            | DataType::DateTimeFmt(_)
+            | DataType::NaiveDateTimeFmt(_) => Ok(Arrow2Type::Date64),
+            DataType::Str => Ok(Arrow2Type::Utf8),
+            DataType::Bytes => Ok(Arrow2Type::Binary),
+            DataType::LargeBytes => Ok(Arrow2Type::LargeBinary),
+            DataType::Decimal128 { precision, scale } => {
+                if *scale < 0 {
+                    fail!("Arrow2 does not support a negative Decimal128 scale");
+                }
+                Ok(Arrow2Type::Decimal(*precision as usize, *scale as usize))
+            }
+            DataType::Arrow2(data_type) => Ok(data_type.clone()),
+            #[cfg(feature = "arrow")]
+            DataType::Arrow(_) => fail!("Cannot convert an Arrow data type to an Arrow2 type"),
+        }
+    }
+}
+
+fn build_primitive<T: NativeType>(
+    values: &[Value],
+    data_type: Arrow2Type,
+    convert: impl Fn(&Value) -> Result<Option<T>>,
+) -> Result<Box<dyn Array>> {
+    let values = values.iter().map(convert).collect::<Result<Vec<_>>>()?;
+    Ok(Box::new(PrimitiveArray::<T>::from(values).to(data_type)))
+}
+
+/// Build an arrow2 array of `data_type` from the captured column values
+pub(crate) fn build_array(data_type: &DataType, values: &[Value]) -> Result<Box<dyn Array>> {
+    let arrow2_type = Arrow2Type::try_from(data_type)?;
+
+    match data_type {
+        DataType::Bool => {
+            let values = values
+                .iter()
+                .map(|value| match value {
+                    Value::Null => Ok(None),
+                    Value::Bool(v) => Ok(Some(*v)),
+                    other => fail!("Cannot build a Bool column from {:?}", other),
+                })
+                .collect::<Result<Vec<_>>>()?;
+            Ok(Box::new(BooleanArray::from(values)))
+        }
+        DataType::I8 => build_primitive::<i8>(values, arrow2_type, |value| match value {
+            Value::Null => Ok(None),
+            Value::I64(v) => Ok(Some(*v as i8)),
+            Value::U64(v) => Ok(Some(*v as i8)),
+            other => fail!("Cannot build an I8 column from {:?}", other),
+        }),
+        DataType::I16 => build_primitive::<i16>(values, arrow2_type, |value| match value {
+            Value::Null => Ok(None),
+            Value::I64(v) => Ok(Some(*v as i16)),
+            Value::U64(v) => Ok(Some(*v as i16)),
+            other => fail!("Cannot build an I16 column from {:?}", other),
+        }),
+        DataType::I32 => build_primitive::<i32>(values, arrow2_type, |value| match value {
+            Value::Null => Ok(None),
+            Value::I64(v) => Ok(Some(*v as i32)),
+            Value::U64(v) => Ok(Some(*v as i32)),
+            other => fail!("Cannot build an I32 column from {:?}", other),
+        }),
+        DataType::I64 => build_primitive::<i64>(values, arrow2_type, |value| match value {
+            Value::Null => Ok(None),
+            Value::I64(v) => Ok(Some(*v)),
+            Value::U64(v) => Ok(Some(*v as i64)),
+            other => fail!("Cannot build an I64 column from {:?}", other),
+        }),
+        DataType::U8 => build_primitive::<u8>(values, arrow2_type, |value| match value {
+            Value::Null => Ok(None),
+            Value::I64(v) => Ok(Some(*v as u8)),
+            Value::U64(v) => Ok(Some(*v as u8)),
+            other => fail!("Cannot build a U8 column from {:?}", other),
+        }),
+        DataType::U16 => build_primitive::<u16>(values, arrow2_type, |value| match value {
+            Value::Null => Ok(None),
+            Value::I64(v) => Ok(Some(*v as u16)),
+            Value::U64(v) => Ok(Some(*v as u16)),
+            other => fail!("Cannot build a U16 column from {:?}", other),
+        }),
+        DataType::U32 => build_primitive::<u32>(values, arrow2_type, |value| match value {
+            Value::Null => Ok(None),
+            Value::I64(v) => Ok(Some(*v as u32)),
+            Value::U64(v) => Ok(Some(*v as u32)),
+            other => fail!("Cannot build a U32 column from {:?}", other),
+        }),
+        DataType::U64 => build_primitive::<u64>(values, arrow2_type, |value| match value {
+            Value::Null => Ok(None),
+            Value::I64(v) => Ok(Some(*v as u64)),
+            Value::U64(v) => Ok(Some(*v)),
+            other => fail!("Cannot build a U64 column from {:?}", other),
+        }),
+        DataType::F32 => build_primitive::<f32>(values, arrow2_type, |value| match value {
+            Value::Null => Ok(None),
+            Value::F64(v) => Ok(Some(*v as f32)),
+            other => fail!("Cannot build an F32 column from {:?}", other),
+        }),
+        DataType::F64 => build_primitive::<f64>(values, arrow2_type, |value| match value {
+            Value::Null => Ok(None),
+            Value::F64(v) => Ok(Some(*v)),
+            other => fail!("Cannot build an F64 column from {:?}", other),
+        }),
+        DataType::Str => {
+            let values = values
+                .iter()
+                .map(|value| match value {
+                    Value::Null => Ok(None),
+                    Value::Str(v) => Ok(Some(v.clone())),
+                    other => fail!("Cannot build a Str column from {:?}", other),
+                })
+                .collect::<Result<Vec<_>>>()?;
+            Ok(Box::new(Utf8Array::<i32>::from(values)))
+        }
+        DataType::Bytes => {
+            let values = values
+                .iter()
+                .map(|value| match value {
+                    Value::Null => Ok(None),
+                    Value::Bytes(v) => Ok(Some(v.clone())),
+                    other => fail!("Cannot build a Bytes column from {:?}", other),
+                })
+                .collect::<Result<Vec<_>>>()?;
+            Ok(Box::new(BinaryArray::<i32>::from(values)))
+        }
+        DataType::LargeBytes => {
+            let values = values
+                .iter()
+                .map(|value| match value {
+                    Value::Null => Ok(None),
+                    Value::Bytes(v) => Ok(Some(v.clone())),
+                    other => fail!("Cannot build a LargeBytes column from {:?}", other),
+                })
+                .collect::<Result<Vec<_>>>()?;
+            Ok(Box::new(BinaryArray::<i64>::from(values)))
+        }
+        DataType::DateTimeStr => build_primitive::<i64>(values, arrow2_type, |value| match value {
+            Value::Null => Ok(None),
+            Value::Str(v) => Ok(Some(
+                chrono::DateTime::parse_from_rfc3339(v)
+                    .map_err(|err| format!("Invalid RFC 3339 date time {v:?}: {err}"))?
+                    .naive_utc()
+                    .timestamp_millis(),
+            )),
+            other => fail!("Cannot build a DateTimeStr column from {:?}", other),
+        }),
+        DataType::NaiveDateTimeStr => {
+            build_primitive::<i64>(values, arrow2_type, |value| match value {
+                Value::Null => Ok(None),
+                Value::Str(v) => Ok(Some(parse_naive_datetime(v)?.timestamp_millis())),
+                other => fail!("Cannot build a NaiveDateTimeStr column from {:?}", other),
+            })
+        }
+        DataType::DateTimeMilliseconds => {
+            build_primitive::<i64>(values, arrow2_type, |value| match value {
+                Value::Null => Ok(None),
+                Value::I64(v) => Ok(Some(*v)),
+                other => fail!("Cannot build a DateTimeMilliseconds column from {:?}", other),
+            })
+        }
+        DataType::DateTimeFmt(fmt) | DataType::NaiveDateTimeFmt(fmt) => {
+            build_primitive::<i64>(values, arrow2_type, |value| match value {
+                Value::Null => Ok(None),
+                Value::Str(v) => Ok(Some(
+                    chrono::NaiveDateTime::parse_from_str(v, fmt)
+                        .map_err(|err| format!("Cannot parse {v:?} with format {fmt:?}: {err}"))?
+                        .timestamp_millis(),
+                )),
+                other => fail!("Cannot build a date time column from {:?}", other),
+            })
+        }
+        DataType::Decimal128 { precision, scale } => {
+            build_primitive::<i128>(values, arrow2_type, |value| match value {
+                Value::Null => Ok(None),
+                other => Ok(Some(value_to_decimal_i128(other, *precision, *scale)?)),
+            })
+        }
+        #[cfg(feature = "arrow")]
+        DataType::Arrow(_) => fail!("Cannot build an Arrow2 column from an Arrow data type"),
+        DataType::Arrow2(_) => fail!("Building raw Arrow2 columns is not supported"),
+    }
+}
+
+/// Extract the captured column values of `data_type` back out of an arrow2 array
+pub(crate) fn extract_values(array: &dyn Array, data_type: &DataType) -> Result<Vec<Value>> {
+    macro_rules! extract_primitive {
+        ($ty:ty) => {{
+            let array = downcast::<PrimitiveArray<$ty>>(array, data_type)?;
+            Ok(array
+                .iter()
+                .map(|v| v.map(|v| Value::I64(*v as i64)).unwrap_or(Value::Null))
+                .collect())
+        }};
+    }
+
+    match data_type {
+        DataType::Bool => {
+            let array = downcast::<BooleanArray>(array, data_type)?;
+            Ok(array
+                .iter()
+                .map(|v| v.map(Value::Bool).unwrap_or(Value::Null))
+                .collect())
+        }
+        DataType::I8 => extract_primitive!(i8),
+        DataType::I16 => extract_primitive!(i16),
+        DataType::I32 => extract_primitive!(i32),
+        DataType::I64 => extract_primitive!(i64),
+        DataType::U8 => extract_primitive!(u8),
+        DataType::U16 => extract_primitive!(u16),
+        DataType::U32 => extract_primitive!(u32),
+        DataType::U64 => {
+            let array = downcast::<PrimitiveArray<u64>>(array, data_type)?;
+            Ok(array
+                .iter()
+                .map(|v| v.map(|v| Value::U64(*v)).unwrap_or(Value::Null))
+                .collect())
+        }
+        DataType::F32 => {
+            let array = downcast::<PrimitiveArray<f32>>(array, data_type)?;
+            Ok(array
+                .iter()
+                .map(|v| v.map(|v| Value::F64(*v as f64)).unwrap_or(Value::Null))
+                .collect())
+        }
+        DataType::F64 => {
+            let array = downcast::<PrimitiveArray<f64>>(array, data_type)?;
+            Ok(array
+                .iter()
+                .map(|v| v.map(|v| Value::F64(*v)).unwrap_or(Value::Null))
+                .collect())
+        }
+        DataType::Str => {
+            let array = downcast::<Utf8Array<i32>>(array, data_type)?;
+            Ok(array
+                .iter()
+                .map(|v| v.map(|v| Value::Str(v.to_owned())).unwrap_or(Value::Null))
+                .collect())
+        }
+        DataType::Bytes => {
+            let array = downcast::<BinaryArray<i32>>(array, data_type)?;
+            Ok(array
+                .iter()
+                .map(|v| v.map(|v| Value::Bytes(v.to_owned())).unwrap_or(Value::Null))
+                .collect())
+        }
+        DataType::LargeBytes => {
+            let array = downcast::<BinaryArray<i64>>(array, data_type)?;
+            Ok(array
+                .iter()
+                .map(|v| v.map(|v| Value::Bytes(v.to_owned())).unwrap_or(Value::Null))
+                .collect())
+        }
+        DataType::DateTimeStr => {
+            let array = downcast::<PrimitiveArray<i64>>(array, data_type)?;
+            Ok(array
+                .iter()
+                .map(|v| {
+                    v.map(|millis| {
+                        let datetime = millis_to_naive_datetime(*millis);
+                        Value::Str(
+                            chrono::DateTime::<chrono::Utc>::from_utc(datetime, chrono::Utc)
+                                .to_rfc3339(),
+                        )
+                    })
+                    .unwrap_or(Value::Null)
+                })
+                .collect())
+        }
+        DataType::NaiveDateTimeStr => {
+            let array = downcast::<PrimitiveArray<i64>>(array, data_type)?;
+            Ok(array
+                .iter()
+                .map(|v| {
+                    v.map(|millis| {
+                        Value::Str(format_naive_datetime(&millis_to_naive_datetime(*millis)))
+                    })
+                    .unwrap_or(Value::Null)
+                })
+                .collect())
+        }
+        DataType::DateTimeMilliseconds => {
+            let array = downcast::<PrimitiveArray<i64>>(array, data_type)?;
+            Ok(array
+                .iter()
+                .map(|v| v.map(|v| Value::I64(*v)).unwrap_or(Value::Null))
+                .collect())
+        }
+        DataType::DateTimeFmt(fmt) | DataType::NaiveDateTimeFmt(fmt) => {
+            let array = downcast::<PrimitiveArray<i64>>(array, data_type)?;
+            Ok(array
+                .iter()
+                .map(|v| {
+                    v.map(|millis| {
+                        Value::Str(millis_to_naive_datetime(*millis).format(fmt).to_string())
+                    })
+                    .unwrap_or(Value::Null)
+                })
+                .collect())
+        }
+        DataType::Decimal128 { scale, .. } => {
+            let array = downcast::<PrimitiveArray<i128>>(array, data_type)?;
+            Ok(array
+                .iter()
+                .map(|v| {
+                    v.map(|v| Value::Str(i128_to_decimal_str(*v, *scale)))
+                        .unwrap_or(Value::Null)
+                })
+                .collect())
+        }
+        #[cfg(feature = "arrow")]
+        DataType::Arrow(_) => fail!("Cannot extract an Arrow data type from an Arrow2 column"),
+        DataType::Arrow2(_) => fail!("Extracting raw Arrow2 columns is not supported"),
+    }
+}
+
+impl TryFrom<&Arrow2Type> for DataType {
+    type Error = crate::Error;
+
+    fn try_from(data_type: &Arrow2Type) -> Result<Self> {
+        match data_type {
+            Arrow2Type::Boolean => Ok(DataType::Bool),
+            Arrow2Type::Int8 => Ok(DataType::I8),
+            Arrow2Type::Int16 => Ok(DataType::I16),
+            Arrow2Type::Int32 => Ok(DataType::I32),
+            Arrow2Type::Int64 => Ok(DataType::I64),
+            Arrow2Type::UInt8 => Ok(DataType::U8),
+            Arrow2Type::UInt16 => Ok(DataType::U16),
+            Arrow2Type::UInt32 => Ok(DataType::U32),
+            Arrow2Type::UInt64 => Ok(DataType::U64),
+            Arrow2Type::Float32 => Ok(DataType::F32),
+            Arrow2Type::Float64 => Ok(DataType::F64),
+            Arrow2Type::Utf8 => Ok(DataType::Str),
+            Arrow2Type::Binary => Ok(DataType::Bytes),
+            Arrow2Type::LargeBinary => Ok(DataType::LargeBytes),
+            // The embedded IPC / arrow2 schema only carries the raw Arrow
+            // data type, not which of this crate's date time variants was
+            // originally used to build the column. `DateTimeMilliseconds`
+            // is the only variant that round-trips without reparsing a
+            // string, so it is used as the default when converting back.
+            Arrow2Type::Date64 => Ok(DataType::DateTimeMilliseconds),
+            Arrow2Type::Decimal(precision, scale) => Ok(DataType::Decimal128 {
+                precision: *precision as u8,
+                scale: *scale as i8,
+            }),
+            other => Ok(DataType::Arrow2(other.clone())),
+        }
+    }
+}
+
+fn downcast<'a, A: 'static>(array: &'a dyn Array, data_type: &DataType) -> Result<&'a A> {
+    array
+        .as_any()
+        .downcast_ref::<A>()
+        .ok_or_else(|| format!("Expected an array matching {data_type}, found a differently typed array").into())
+}
+
+impl TryFrom<&arrow2::datatypes::Schema> for super::Schema {
+    type Error = crate::Error;
+
+    fn try_from(schema: &arrow2::datatypes::Schema) -> Result<Self> {
+        let mut result = super::Schema::new();
+        for field in &schema.fields {
+            let data_type = DataType::try_from(&field.data_type)?;
+            result.add_field(&field.name, Some(data_type), Some(field.is_nullable));
+        }
+        Ok(result)
+    }
+}
@@ -0,0 +1,380 @@
+//! Conversion between [DataType] and the `arrow` crate's data types, and the
+//! column builders / extractors used by [crate::arrow::to_record_batch] /
+//! [crate::arrow::from_record_batch]
+//!
+use std::sync::Arc;
+
+use arrow::array::{
+    Array, ArrayRef, BinaryArray, BooleanArray, Date64Array, Decimal128Array, Float32Array,
+    Float64Array, Int16Array, Int32Array, Int64Array, Int8Array, LargeBinaryArray, StringArray,
+    UInt16Array, UInt32Array, UInt64Array, UInt8Array,
+};
+use arrow::datatypes::DataType as ArrowType;
+
+use crate::{fail, Result};
+
+use super::{
+    datetime::{format_naive_datetime, millis_to_naive_datetime, naive_datetime_to_millis, parse_naive_datetime},
+    decimal::{i128_to_decimal_str, value_to_decimal_i128},
+    value::Value,
+    DataType,
+};
+
+impl TryFrom<&DataType> for ArrowType {
+    type Error = crate::Error;
+
+    fn try_from(data_type: &DataType) -> Result<Self> {
+        match data_type {
+            DataType::Bool => Ok(ArrowType::Boolean),
+            DataType::I8 => Ok(ArrowType::Int8),
+            DataType::I16 => Ok(ArrowType::Int16),
+            DataType::I32 => Ok(ArrowType::Int32),
+            DataType::I64 => Ok(ArrowType::Int64),
+            DataType::U8 => Ok(ArrowType::UInt8),
+            DataType::U16 => Ok(ArrowType::UInt16),
+            DataType::U32 => Ok(ArrowType::UInt32),
+            DataType::U64 => Ok(ArrowType::UInt64),
+            DataType::F32 => Ok(ArrowType::Float32),
+            DataType::F64 => Ok(ArrowType::Float64),
+            DataType::DateTimeStr
+            | DataType::NaiveDateTimeStr
+            | DataType::DateTimeMilliseconds
+            | DataType::DateTimeFmt(_)
+            | DataType::NaiveDateTimeFmt(_) => Ok(ArrowType::Date64),
+            DataType::Str => Ok(ArrowType::Utf8),
+            DataType::Bytes => Ok(ArrowType::Binary),
+            DataType::LargeBytes => Ok(ArrowType::LargeBinary),
+            DataType::Decimal128 { precision, scale } => {
+                Ok(ArrowType::Decimal128(*precision, *scale))
+            }
+            DataType::Arrow(data_type) => Ok(data_type.clone()),
+            #[cfg(feature = "arrow2")]
+            DataType::Arrow2(_) => fail!("Cannot convert an Arrow2 data type to an Arrow type"),
+        }
+    }
+}
+
+/// Build an Arrow array of `data_type` from the captured column values
+///
+/// `values` holds one entry per row, in the same order as the rows passed to
+/// [crate::arrow::to_record_batch]; a missing field is represented as
+/// [Value::Null].
+///
+pub(crate) fn build_array(data_type: &DataType, values: &[Value]) -> Result<ArrayRef> {
+    macro_rules! build_int {
+        ($array:ty, $variant:ident, $cast:ty) => {
+            Ok(Arc::new(
+                values
+                    .iter()
+                    .map(|value| match value {
+                        Value::Null => Ok(None),
+                        Value::I64(v) => Ok(Some(*v as $cast)),
+                        Value::U64(v) => Ok(Some(*v as $cast)),
+                        other => fail!("Cannot build a {} column from {:?}", stringify!($variant), other),
+                    })
+                    .collect::<Result<Vec<Option<$cast>>>>()?
+                    .into_iter()
+                    .collect::<$array>(),
+            ) as ArrayRef)
+        };
+    }
+
+    match data_type {
+        DataType::Bool => Ok(Arc::new(
+            values
+                .iter()
+                .map(|value| match value {
+                    Value::Null => Ok(None),
+                    Value::Bool(v) => Ok(Some(*v)),
+                    other => fail!("Cannot build a Bool column from {:?}", other),
+                })
+                .collect::<Result<Vec<Option<bool>>>>()?
+                .into_iter()
+                .collect::<BooleanArray>(),
+        ) as ArrayRef),
+        DataType::I8 => build_int!(Int8Array, I8, i8),
+        DataType::I16 => build_int!(Int16Array, I16, i16),
+        DataType::I32 => build_int!(Int32Array, I32, i32),
+        DataType::I64 => build_int!(Int64Array, I64, i64),
+        DataType::U8 => build_int!(UInt8Array, U8, u8),
+        DataType::U16 => build_int!(UInt16Array, U16, u16),
+        DataType::U32 => build_int!(UInt32Array, U32, u32),
+        DataType::U64 => build_int!(UInt64Array, U64, u64),
+        DataType::F32 => Ok(Arc::new(
+            values
+                .iter()
+                .map(|value| match value {
+                    Value::Null => Ok(None),
+                    Value::F64(v) => Ok(Some(*v as f32)),
+                    other => fail!("Cannot build a F32 column from {:?}", other),
+                })
+                .collect::<Result<Vec<Option<f32>>>>()?
+                .into_iter()
+                .collect::<Float32Array>(),
+        ) as ArrayRef),
+        DataType::F64 => Ok(Arc::new(
+            values
+                .iter()
+                .map(|value| match value {
+                    Value::Null => Ok(None),
+                    Value::F64(v) => Ok(Some(*v)),
+                    other => fail!("Cannot build a F64 column from {:?}", other),
+                })
+                .collect::<Result<Vec<Option<f64>>>>()?
+                .into_iter()
+                .collect::<Float64Array>(),
+        ) as ArrayRef),
+        DataType::Str => Ok(Arc::new(
+            values
+                .iter()
+                .map(|value| match value {
+                    Value::Null => Ok(None),
+                    Value::Str(v) => Ok(Some(v.clone())),
+                    other => fail!("Cannot build a Str column from {:?}", other),
+                })
+                .collect::<Result<Vec<Option<String>>>>()?
+                .into_iter()
+                .collect::<StringArray>(),
+        ) as ArrayRef),
+        DataType::Bytes => Ok(Arc::new(
+            values
+                .iter()
+                .map(|value| match value {
+                    Value::Null => Ok(None),
+                    Value::Bytes(v) => Ok(Some(v.clone())),
+                    other => fail!("Cannot build a Bytes column from {:?}", other),
+                })
+                .collect::<Result<Vec<Option<Vec<u8>>>>>()?
+                .iter()
+                .map(|v| v.as_deref())
+                .collect::<BinaryArray>(),
+        ) as ArrayRef),
+        DataType::LargeBytes => Ok(Arc::new(
+            values
+                .iter()
+                .map(|value| match value {
+                    Value::Null => Ok(None),
+                    Value::Bytes(v) => Ok(Some(v.clone())),
+                    other => fail!("Cannot build a LargeBytes column from {:?}", other),
+                })
+                .collect::<Result<Vec<Option<Vec<u8>>>>>()?
+                .iter()
+                .map(|v| v.as_deref())
+                .collect::<LargeBinaryArray>(),
+        ) as ArrayRef),
+        DataType::DateTimeStr => Ok(Arc::new(
+            values
+                .iter()
+                .map(|value| match value {
+                    Value::Null => Ok(None),
+                    Value::Str(v) => Ok(Some(
+                        chrono::DateTime::parse_from_rfc3339(v)
+                            .map_err(|err| format!("Invalid RFC 3339 date time {v:?}: {err}"))?
+                            .naive_utc()
+                            .timestamp_millis(),
+                    )),
+                    other => fail!("Cannot build a DateTimeStr column from {:?}", other),
+                })
+                .collect::<Result<Vec<Option<i64>>>>()?
+                .into_iter()
+                .collect::<Date64Array>(),
+        ) as ArrayRef),
+        DataType::NaiveDateTimeStr => Ok(Arc::new(
+            values
+                .iter()
+                .map(|value| match value {
+                    Value::Null => Ok(None),
+                    Value::Str(v) => Ok(Some(naive_datetime_to_millis(&parse_naive_datetime(v)?))),
+                    other => fail!("Cannot build a NaiveDateTimeStr column from {:?}", other),
+                })
+                .collect::<Result<Vec<Option<i64>>>>()?
+                .into_iter()
+                .collect::<Date64Array>(),
+        ) as ArrayRef),
+        DataType::DateTimeMilliseconds => Ok(Arc::new(
+            values
+                .iter()
+                .map(|value| match value {
+                    Value::Null => Ok(None),
+                    Value::I64(v) => Ok(Some(*v)),
+                    other => fail!("Cannot build a DateTimeMilliseconds column from {:?}", other),
+                })
+                .collect::<Result<Vec<Option<i64>>>>()?
+                .into_iter()
+                .collect::<Date64Array>(),
+        ) as ArrayRef),
+        DataType::DateTimeFmt(fmt) | DataType::NaiveDateTimeFmt(fmt) => Ok(Arc::new(
+            values
+                .iter()
+                .map(|value| match value {
+                    Value::Null => Ok(None),
+                    Value::Str(v) => Ok(Some(naive_datetime_to_millis(
+                        &chrono::NaiveDateTime::parse_from_str(v, fmt).map_err(|err| {
+                            format!("Cannot parse {v:?} with format {fmt:?}: {err}")
+                        })?,
+                    ))),
+                    other => fail!("Cannot build a date time column from {:?}", other),
+                })
+                .collect::<Result<Vec<Option<i64>>>>()?
+                .into_iter()
+                .collect::<Date64Array>(),
+        ) as ArrayRef),
+        DataType::Decimal128 { precision, scale } => {
+            let values = values
+                .iter()
+                .map(|value| match value {
+                    Value::Null => Ok(None),
+                    other => Ok(Some(value_to_decimal_i128(other, *precision, *scale)?)),
+                })
+                .collect::<Result<Vec<Option<i128>>>>()?;
+
+            let array = values
+                .into_iter()
+                .collect::<Decimal128Array>()
+                .with_precision_and_scale(*precision, *scale)
+                .map_err(|err| format!("Invalid Decimal128(precision={precision}, scale={scale}): {err}"))?;
+
+            Ok(Arc::new(array) as ArrayRef)
+        }
+        DataType::Arrow(_) => fail!("Building raw Arrow columns is not supported"),
+        #[cfg(feature = "arrow2")]
+        DataType::Arrow2(_) => fail!("Cannot build an Arrow column from an Arrow2 data type"),
+    }
+}
+
+/// Extract the captured column values of `data_type` back out of an Arrow array
+pub(crate) fn extract_values(array: &dyn Array, data_type: &DataType) -> Result<Vec<Value>> {
+    macro_rules! extract_int {
+        ($array:ty) => {{
+            let array = downcast::<$array>(array, data_type)?;
+            Ok(array
+                .iter()
+                .map(|v| v.map(|v| Value::I64(v as i64)).unwrap_or(Value::Null))
+                .collect())
+        }};
+    }
+
+    match data_type {
+        DataType::Bool => {
+            let array = downcast::<BooleanArray>(array, data_type)?;
+            Ok(array
+                .iter()
+                .map(|v| v.map(Value::Bool).unwrap_or(Value::Null))
+                .collect())
+        }
+        DataType::I8 => extract_int!(Int8Array),
+        DataType::I16 => extract_int!(Int16Array),
+        DataType::I32 => extract_int!(Int32Array),
+        DataType::I64 => extract_int!(Int64Array),
+        DataType::U8 => extract_int!(UInt8Array),
+        DataType::U16 => extract_int!(UInt16Array),
+        DataType::U32 => extract_int!(UInt32Array),
+        DataType::U64 => {
+            let array = downcast::<UInt64Array>(array, data_type)?;
+            Ok(array
+                .iter()
+                .map(|v| v.map(Value::U64).unwrap_or(Value::Null))
+                .collect())
+        }
+        DataType::F32 => {
+            let array = downcast::<Float32Array>(array, data_type)?;
+            Ok(array
+                .iter()
+                .map(|v| v.map(|v| Value::F64(v as f64)).unwrap_or(Value::Null))
+                .collect())
+        }
+        DataType::F64 => {
+            let array = downcast::<Float64Array>(array, data_type)?;
+            Ok(array
+                .iter()
+                .map(|v| v.map(Value::F64).unwrap_or(Value::Null))
+                .collect())
+        }
+        DataType::Str => {
+            let array = downcast::<StringArray>(array, data_type)?;
+            Ok(array
+                .iter()
+                .map(|v| v.map(|v| Value::Str(v.to_owned())).unwrap_or(Value::Null))
+                .collect())
+        }
+        DataType::Bytes => {
+            let array = downcast::<BinaryArray>(array, data_type)?;
+            Ok(array
+                .iter()
+                .map(|v| v.map(|v| Value::Bytes(v.to_owned())).unwrap_or(Value::Null))
+                .collect())
+        }
+        DataType::LargeBytes => {
+            let array = downcast::<LargeBinaryArray>(array, data_type)?;
+            Ok(array
+                .iter()
+                .map(|v| v.map(|v| Value::Bytes(v.to_owned())).unwrap_or(Value::Null))
+                .collect())
+        }
+        DataType::DateTimeStr => {
+            let array = downcast::<Date64Array>(array, data_type)?;
+            Ok(array
+                .iter()
+                .map(|v| {
+                    v.map(|millis| {
+                        let datetime = millis_to_naive_datetime(millis);
+                        Value::Str(
+                            chrono::DateTime::<chrono::Utc>::from_utc(datetime, chrono::Utc)
+                                .to_rfc3339(),
+                        )
+                    })
+                    .unwrap_or(Value::Null)
+                })
+                .collect())
+        }
+        DataType::NaiveDateTimeStr => {
+            let array = downcast::<Date64Array>(array, data_type)?;
+            Ok(array
+                .iter()
+                .map(|v| {
+                    v.map(|millis| Value::Str(format_naive_datetime(&millis_to_naive_datetime(millis))))
+                        .unwrap_or(Value::Null)
+                })
+                .collect())
+        }
+        DataType::DateTimeMilliseconds => {
+            let array = downcast::<Date64Array>(array, data_type)?;
+            Ok(array
+                .iter()
+                .map(|v| v.map(Value::I64).unwrap_or(Value::Null))
+                .collect())
+        }
+        DataType::DateTimeFmt(fmt) | DataType::NaiveDateTimeFmt(fmt) => {
+            let array = downcast::<Date64Array>(array, data_type)?;
+            Ok(array
+                .iter()
+                .map(|v| {
+                    v.map(|millis| {
+                        Value::Str(millis_to_naive_datetime(millis).format(fmt).to_string())
+                    })
+                    .unwrap_or(Value::Null)
+                })
+                .collect())
+        }
+        DataType::Decimal128 { scale, .. } => {
+            let array = downcast::<Decimal128Array>(array, data_type)?;
+            Ok(array
+                .iter()
+                .map(|v| {
+                    v.map(|v| Value::Str(i128_to_decimal_str(v, *scale)))
+                        .unwrap_or(Value::Null)
+                })
+                .collect())
+        }
+        DataType::Arrow(_) => fail!("Extracting raw Arrow columns is not supported"),
+        #[cfg(feature = "arrow2")]
+        DataType::Arrow2(_) => fail!("Cannot extract an Arrow2 data type from an Arrow column"),
+    }
+}
+
+fn downcast<'a, A: 'static>(array: &'a dyn Array, data_type: &DataType) -> Result<&'a A> {
+    array
+        .as_any()
+        .downcast_ref::<A>()
+        .ok_or_else(|| format!("Expected an array matching {data_type}, found a differently typed array").into())
+}
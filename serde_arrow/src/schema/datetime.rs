@@ -0,0 +1,30 @@
+//! Shared conversions between naive date time strings and the milliseconds
+//! since the epoch stored in Arrow's `Date64`
+//!
+use chrono::NaiveDateTime;
+
+use crate::Result;
+
+/// The naive format emitted by chrono's default serde implementation for
+/// `NaiveDateTime` (no time zone, e.g., `"2016-07-08T09:10:11"`)
+const NAIVE_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.f";
+
+pub(crate) fn parse_naive_datetime(s: &str) -> Result<NaiveDateTime> {
+    NaiveDateTime::parse_from_str(s, NAIVE_FORMAT)
+        .map_err(|err| format!("Invalid naive date time {s:?}: {err}").into())
+}
+
+pub(crate) fn format_naive_datetime(datetime: &NaiveDateTime) -> String {
+    datetime.format(NAIVE_FORMAT).to_string()
+}
+
+pub(crate) fn naive_datetime_to_millis(datetime: &NaiveDateTime) -> i64 {
+    datetime.timestamp_millis()
+}
+
+pub(crate) fn millis_to_naive_datetime(millis: i64) -> NaiveDateTime {
+    NaiveDateTime::from_timestamp(
+        millis.div_euclid(1000),
+        (millis.rem_euclid(1000) * 1_000_000) as u32,
+    )
+}
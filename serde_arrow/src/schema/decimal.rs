@@ -0,0 +1,105 @@
+//! Conversions between `Decimal128` scaled integers and the string / integer
+//! representations serde may emit for a decimal field
+//!
+use crate::{fail, Result};
+
+use super::value::Value;
+
+/// Convert a captured column value into an already-scaled `i128`, checking
+/// that it fits within `precision` digits
+///
+/// `value` may be a decimal string (e.g., `"-12.340"`) or an already-scaled
+/// integer (e.g., `-12340` for `scale == 3`); unlike the string, an integer
+/// value is taken to already be scaled and is used as-is.
+///
+pub(crate) fn value_to_decimal_i128(value: &Value, precision: u8, scale: i8) -> Result<i128> {
+    let scaled = match value {
+        Value::Str(s) => decimal_str_to_i128(s, scale)?,
+        Value::I64(v) => *v as i128,
+        Value::U64(v) => *v as i128,
+        other => fail!("Cannot build a Decimal128 value from {:?}", other),
+    };
+    check_precision(scaled, precision)?;
+    Ok(scaled)
+}
+
+/// Check that `value` fits within `precision` decimal digits
+fn check_precision(value: i128, precision: u8) -> Result<()> {
+    let limit = 10i128.pow(precision as u32);
+    if value <= -limit || value >= limit {
+        fail!("Decimal128 value {value} has more digits than the configured precision {precision}");
+    }
+    Ok(())
+}
+
+/// Parse a decimal string (e.g., `"-12.34"`) into an `i128` scaled by `scale`
+/// decimal digits (e.g., `-12340` for `scale == 3`)
+pub(crate) fn decimal_str_to_i128(s: &str, scale: i8) -> Result<i128> {
+    let scale = scale as usize;
+    let (sign, s) = match s.strip_prefix('-') {
+        Some(rest) => (-1i128, rest),
+        None => (1i128, s.strip_prefix('+').unwrap_or(s)),
+    };
+
+    let (int_part, frac_part) = match s.split_once('.') {
+        Some((int_part, frac_part)) => (int_part, frac_part),
+        None => (s, ""),
+    };
+
+    if frac_part.len() > scale {
+        fail!(
+            "Decimal string {:?} has more fractional digits than the configured scale {}",
+            s,
+            scale
+        );
+    }
+    if !int_part.chars().all(|c| c.is_ascii_digit())
+        || !frac_part.chars().all(|c| c.is_ascii_digit())
+    {
+        fail!("Invalid decimal string {:?}", s);
+    }
+
+    let mut digits = String::with_capacity(int_part.len() + scale);
+    digits.push_str(int_part);
+    digits.push_str(frac_part);
+    for _ in 0..(scale - frac_part.len()) {
+        digits.push('0');
+    }
+
+    let magnitude: i128 = if digits.is_empty() {
+        0
+    } else {
+        digits
+            .parse()
+            .map_err(|err| format!("Invalid decimal string {s:?}: {err}"))?
+    };
+
+    Ok(sign * magnitude)
+}
+
+/// Format a scaled `i128` (as stored in `Decimal128`) back into a decimal
+/// string, the inverse of [decimal_str_to_i128]
+pub(crate) fn i128_to_decimal_str(value: i128, scale: i8) -> String {
+    let scale = scale as usize;
+    let negative = value < 0;
+    let digits = value.unsigned_abs().to_string();
+    let digits = if digits.len() <= scale {
+        format!("{:0>width$}", digits, width = scale + 1)
+    } else {
+        digits
+    };
+
+    let split = digits.len() - scale;
+    let (int_part, frac_part) = digits.split_at(split);
+
+    let mut result = String::new();
+    if negative {
+        result.push('-');
+    }
+    result.push_str(int_part);
+    if scale > 0 {
+        result.push('.');
+        result.push_str(frac_part);
+    }
+    result
+}
@@ -1,8 +1,17 @@
 #[cfg(feature = "arrow")]
-mod arrow_support;
+pub(crate) mod arrow_support;
 
 #[cfg(feature = "arrow2")]
-mod arrow2_support;
+pub(crate) mod arrow2_support;
+
+#[cfg(any(feature = "arrow", feature = "arrow2"))]
+mod datetime;
+
+#[cfg(any(feature = "arrow", feature = "arrow2"))]
+mod decimal;
+
+#[cfg(any(feature = "arrow", feature = "arrow2"))]
+pub(crate) mod value;
 
 use std::collections::{HashMap, HashSet};
 
@@ -43,8 +52,41 @@ pub enum DataType {
     NaiveDateTimeStr,
     /// A date time as non-leap milliseconds since the epoch (mapped to Arrow's Date64)
     DateTimeMilliseconds,
+    /// A date time as a string in a custom format (requires chrono, mapped to
+    /// Arrow's Date64)
+    ///
+    /// The format string follows the syntax of
+    /// [`chrono::format::strftime`] and must describe both a date and a time
+    /// (e.g., `"%Y-%m-%d %H:%M:%S"`). The string is parsed and formatted as a
+    /// naive date time, exactly as for [DataType::NaiveDateTimeFmt]: time zone
+    /// information is not applied, and formats with a time zone specifier
+    /// (e.g., `%z`) are not supported.
+    DateTimeFmt(String),
+    /// A naive (time zone-less) date time as a string in a custom format
+    /// (requires chrono, mapped to Arrow's Date64)
+    ///
+    /// See [DataType::DateTimeFmt] for details on the format string.
+    NaiveDateTimeFmt(String),
     /// A string (mapped to Arrow's UTF8)
     Str,
+    /// Raw bytes, e.g., as emitted by `serde_bytes::Bytes` /
+    /// `serde_bytes::ByteBuf` (mapped to Arrow's Binary)
+    Bytes,
+    /// Raw bytes, stored with 64 bit offsets (mapped to Arrow's LargeBinary)
+    ///
+    /// Use this variant instead of [DataType::Bytes] if the values of a column
+    /// may together exceed 2 GiB.
+    LargeBytes,
+    /// A fixed-point decimal number (mapped to Arrow's `Decimal128`)
+    ///
+    /// `precision` is the total number of decimal digits (1 to 38) and
+    /// `scale` the number of digits after the decimal point. Values may
+    /// serialize either as a decimal string (e.g., `"-12.340"`) or as an
+    /// already-scaled integer (e.g., `-12340` for `scale: 3`). Since decimals
+    /// cannot be distinguished from plain strings or integers by tracing
+    /// alone, this data type must always be set explicitly via
+    /// [Schema::set_data_type].
+    Decimal128 { precision: u8, scale: i8 },
     #[cfg(feature = "arrow")]
     /// a raw arrow data type
     Arrow(ArrowType),
@@ -69,7 +111,14 @@ impl std::fmt::Display for DataType {
             Self::DateTimeStr => write!(f, "DateTimeStr"),
             Self::NaiveDateTimeStr => write!(f, "NaiveDateTimeStr"),
             Self::DateTimeMilliseconds => write!(f, "DateTimeMilliseconds"),
+            Self::DateTimeFmt(fmt) => write!(f, "DateTimeFmt({fmt})"),
+            Self::NaiveDateTimeFmt(fmt) => write!(f, "NaiveDateTimeFmt({fmt})"),
             Self::Str => write!(f, "Str"),
+            Self::Bytes => write!(f, "Bytes"),
+            Self::LargeBytes => write!(f, "LargeBytes"),
+            Self::Decimal128 { precision, scale } => {
+                write!(f, "Decimal128({precision}, {scale})")
+            }
             #[cfg(feature = "arrow")]
             Self::Arrow(dt) => write!(f, "Arrow({dt})"),
             #[cfg(feature = "arrow2")]
@@ -170,6 +219,12 @@ impl Schema {
         if !self.seen_fields.contains(field) {
             fail!("Cannot set data type for unknown field {}", field);
         }
+        if let DataType::DateTimeFmt(fmt) | DataType::NaiveDateTimeFmt(fmt) = &data_type {
+            validate_datetime_format(fmt)?;
+        }
+        if let DataType::Decimal128 { precision, scale } = &data_type {
+            validate_decimal128(*precision, *scale)?;
+        }
         self.data_type.insert(field.to_owned(), data_type);
         Ok(())
     }
@@ -187,4 +242,137 @@ impl Schema {
         }
         Ok(())
     }
+
+    /// Trace `records` and merge the result into this schema
+    ///
+    /// This is the incremental counterpart to [Schema::from_records]: it
+    /// allows building up a schema from a sequence of batches that may not
+    /// all share the same shape, e.g., when reading chunked input. See
+    /// [Schema::merge] for the exact merging rules.
+    ///
+    pub fn update_from_records<T: Serialize + ?Sized>(&mut self, records: &T) -> Result<()> {
+        let traced = crate::ops::trace_schema(records)?;
+        self.merge(&traced)
+    }
+
+    /// Merge another schema into this one
+    ///
+    /// Fields are unioned, preserving the order in which they were first
+    /// seen across both schemas. For a field present on both sides, the data
+    /// types are reconciled as follows:
+    ///
+    /// - if both sides agree on the data type, it is kept as is
+    /// - if only one side has a data type (the other is unknown, e.g., an
+    ///   all-`None` optional column), the known data type is adopted and the
+    ///   field is marked nullable
+    /// - if the two sides disagree on the data type, this function fails
+    ///   naming both conflicting types
+    ///
+    /// A field present on only one side is carried over and marked nullable,
+    /// since records on the other side did not provide a value for it.
+    ///
+    pub fn merge(&mut self, other: &Schema) -> Result<()> {
+        for field in &other.fields {
+            let other_data_type = other.data_type.get(field);
+
+            if !self.seen_fields.contains(field) {
+                self.seen_fields.insert(field.clone());
+                self.fields.push(field.clone());
+                self.nullable.insert(field.clone());
+            }
+
+            match (self.data_type.get(field), other_data_type) {
+                (Some(left), Some(right)) => {
+                    if left != right {
+                        fail!(
+                            "Cannot merge schemas: field {} has conflicting data types {} and {}",
+                            field,
+                            left,
+                            right
+                        );
+                    }
+                }
+                (None, Some(right)) => {
+                    self.data_type.insert(field.clone(), right.clone());
+                    self.nullable.insert(field.clone());
+                }
+                (Some(_), None) => {
+                    self.nullable.insert(field.clone());
+                }
+                (None, None) => {}
+            }
+
+            if other.nullable.contains(field) {
+                self.nullable.insert(field.clone());
+            }
+        }
+
+        for field in &self.fields {
+            if !other.seen_fields.contains(field) {
+                self.nullable.insert(field.clone());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Check that a chrono format string describes a full date and time
+///
+/// This is used to validate [DataType::DateTimeFmt] and
+/// [DataType::NaiveDateTimeFmt] as early as possible, since a format that only
+/// covers a date or only a time cannot be mapped to Arrow's `Date64`
+/// (milliseconds since the epoch).
+///
+fn validate_datetime_format(fmt: &str) -> Result<()> {
+    use chrono::NaiveDateTime;
+
+    let reference = NaiveDateTime::from_timestamp(1_614_000_000, 0);
+    let formatted = reference.format(fmt).to_string();
+
+    let parsed = match NaiveDateTime::parse_from_str(&formatted, fmt) {
+        Ok(parsed) => parsed,
+        Err(err) => fail!(
+            "Invalid datetime format {}: does not round-trip a reference date time ({})",
+            fmt,
+            err
+        ),
+    };
+
+    if parsed != reference {
+        fail!(
+            "Invalid datetime format {}: must describe a full date and time",
+            fmt
+        );
+    }
+
+    Ok(())
+}
+
+/// Check that a `Decimal128` precision / scale pair is representable
+///
+/// Arrow's `Decimal128` stores its value in an `i128`, which can hold at most
+/// 38 decimal digits. `scale` further must not be negative, since this crate
+/// only supports shifting the fractional part to the right when scaling
+/// incoming values.
+///
+fn validate_decimal128(precision: u8, scale: i8) -> Result<()> {
+    if precision == 0 || precision > 38 {
+        fail!(
+            "Invalid Decimal128 precision {}: must be between 1 and 38",
+            precision
+        );
+    }
+    if scale < 0 {
+        fail!("Invalid Decimal128 scale {}: must not be negative", scale);
+    }
+    if i64::from(scale) > i64::from(precision) {
+        fail!(
+            "Invalid Decimal128 scale {} for precision {}: scale must not exceed precision",
+            scale,
+            precision
+        );
+    }
+
+    Ok(())
 }
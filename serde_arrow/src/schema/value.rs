@@ -0,0 +1,432 @@
+//! A small, dynamically typed value used to move data between `serde` and
+//! the column builders in [super::arrow_support] / [super::arrow2_support]
+//!
+//! `Value` is intentionally minimal: it only distinguishes the shapes needed
+//! to build or read back the [DataType](super::DataType) variants this crate
+//! supports, not a general purpose JSON-like model.
+//!
+use std::fmt;
+
+use serde::{
+    de::{self, value::SeqDeserializer, IntoDeserializer, Visitor},
+    ser::{
+        self, SerializeMap, SerializeSeq, SerializeStruct, SerializeStructVariant, SerializeTuple,
+        SerializeTupleStruct, SerializeTupleVariant,
+    },
+    Deserialize, Serialize, Serializer,
+};
+
+use crate::{fail, internal::DuplicateKeyPolicy};
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Value {
+    Null,
+    Bool(bool),
+    I64(i64),
+    U64(u64),
+    F64(f64),
+    Str(String),
+    Bytes(Vec<u8>),
+    Seq(Vec<Value>),
+    Struct(Vec<(String, Value)>),
+}
+
+impl Value {
+    pub(crate) fn field(&self, name: &str) -> Value {
+        match self {
+            Value::Struct(fields) => fields
+                .iter()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value.clone())
+                .unwrap_or(Value::Null),
+            _ => Value::Null,
+        }
+    }
+}
+
+/// Serialize a sequence of records into one [Value] per record
+///
+/// `items` must serialize as a sequence of structs or maps, mirroring what
+/// [crate::trace_schema] expects.
+///
+pub(crate) fn capture_rows<T: Serialize + ?Sized>(items: &T) -> crate::Result<Vec<Value>> {
+    let captured = items
+        .serialize(ValueSerializer)
+        .map_err(|err: Error| err.0)?;
+    match captured {
+        Value::Seq(rows) => Ok(rows),
+        value => fail!("Expected a sequence of records, found {:?}", value),
+    }
+}
+
+/// Reconstruct a single record from a captured [Value]
+pub(crate) fn from_value<'de, T: Deserialize<'de>>(value: Value) -> crate::Result<T> {
+    T::deserialize(value).map_err(|err: Error| err.0)
+}
+
+struct Error(crate::Error);
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+impl std::fmt::Debug for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self.0)
+    }
+}
+impl std::error::Error for Error {}
+impl de::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Error(format!("{msg}").into())
+    }
+}
+impl ser::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Error(format!("{msg}").into())
+    }
+}
+
+struct ValueSerializer;
+
+impl Serializer for ValueSerializer {
+    type Ok = Value;
+    type Error = Error;
+    type SerializeSeq = SeqCapture;
+    type SerializeTuple = SeqCapture;
+    type SerializeTupleStruct = SeqCapture;
+    type SerializeTupleVariant = SeqCapture;
+    type SerializeMap = MapCapture;
+    type SerializeStruct = StructCapture;
+    type SerializeStructVariant = StructCapture;
+
+    fn serialize_bool(self, v: bool) -> Result<Value, Error> {
+        Ok(Value::Bool(v))
+    }
+    fn serialize_i8(self, v: i8) -> Result<Value, Error> {
+        Ok(Value::I64(v as i64))
+    }
+    fn serialize_i16(self, v: i16) -> Result<Value, Error> {
+        Ok(Value::I64(v as i64))
+    }
+    fn serialize_i32(self, v: i32) -> Result<Value, Error> {
+        Ok(Value::I64(v as i64))
+    }
+    fn serialize_i64(self, v: i64) -> Result<Value, Error> {
+        Ok(Value::I64(v))
+    }
+    fn serialize_u8(self, v: u8) -> Result<Value, Error> {
+        Ok(Value::U64(v as u64))
+    }
+    fn serialize_u16(self, v: u16) -> Result<Value, Error> {
+        Ok(Value::U64(v as u64))
+    }
+    fn serialize_u32(self, v: u32) -> Result<Value, Error> {
+        Ok(Value::U64(v as u64))
+    }
+    fn serialize_u64(self, v: u64) -> Result<Value, Error> {
+        Ok(Value::U64(v))
+    }
+    fn serialize_f32(self, v: f32) -> Result<Value, Error> {
+        Ok(Value::F64(v as f64))
+    }
+    fn serialize_f64(self, v: f64) -> Result<Value, Error> {
+        Ok(Value::F64(v))
+    }
+    fn serialize_char(self, v: char) -> Result<Value, Error> {
+        Ok(Value::Str(v.to_string()))
+    }
+    fn serialize_str(self, v: &str) -> Result<Value, Error> {
+        Ok(Value::Str(v.to_owned()))
+    }
+    fn serialize_bytes(self, v: &[u8]) -> Result<Value, Error> {
+        Ok(Value::Bytes(v.to_owned()))
+    }
+    fn serialize_none(self) -> Result<Value, Error> {
+        Ok(Value::Null)
+    }
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Value, Error> {
+        value.serialize(self)
+    }
+    fn serialize_unit(self) -> Result<Value, Error> {
+        Ok(Value::Null)
+    }
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Value, Error> {
+        Ok(Value::Null)
+    }
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+    ) -> Result<Value, Error> {
+        Ok(Value::Str(variant.to_owned()))
+    }
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Value, Error> {
+        value.serialize(self)
+    }
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        value: &T,
+    ) -> Result<Value, Error> {
+        value.serialize(self)
+    }
+    fn serialize_seq(self, len: Option<usize>) -> Result<SeqCapture, Error> {
+        Ok(SeqCapture(Vec::with_capacity(len.unwrap_or(0))))
+    }
+    fn serialize_tuple(self, len: usize) -> Result<SeqCapture, Error> {
+        self.serialize_seq(Some(len))
+    }
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<SeqCapture, Error> {
+        self.serialize_seq(Some(len))
+    }
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        len: usize,
+    ) -> Result<SeqCapture, Error> {
+        self.serialize_seq(Some(len))
+    }
+    fn serialize_map(self, _len: Option<usize>) -> Result<MapCapture, Error> {
+        Ok(MapCapture {
+            fields: Vec::new(),
+            pending_key: None,
+        })
+    }
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<StructCapture, Error> {
+        Ok(StructCapture(Vec::with_capacity(len)))
+    }
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        len: usize,
+    ) -> Result<StructCapture, Error> {
+        Ok(StructCapture(Vec::with_capacity(len)))
+    }
+}
+
+struct SeqCapture(Vec<Value>);
+
+impl SerializeSeq for SeqCapture {
+    type Ok = Value;
+    type Error = Error;
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.0.push(value.serialize(ValueSerializer)?);
+        Ok(())
+    }
+    fn end(self) -> Result<Value, Error> {
+        Ok(Value::Seq(self.0))
+    }
+}
+impl SerializeTuple for SeqCapture {
+    type Ok = Value;
+    type Error = Error;
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        SerializeSeq::serialize_element(self, value)
+    }
+    fn end(self) -> Result<Value, Error> {
+        SerializeSeq::end(self)
+    }
+}
+impl SerializeTupleStruct for SeqCapture {
+    type Ok = Value;
+    type Error = Error;
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        SerializeSeq::serialize_element(self, value)
+    }
+    fn end(self) -> Result<Value, Error> {
+        SerializeSeq::end(self)
+    }
+}
+impl SerializeTupleVariant for SeqCapture {
+    type Ok = Value;
+    type Error = Error;
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        SerializeSeq::serialize_element(self, value)
+    }
+    fn end(self) -> Result<Value, Error> {
+        SerializeSeq::end(self)
+    }
+}
+
+struct MapCapture {
+    fields: Vec<(String, Value)>,
+    pending_key: Option<String>,
+}
+
+impl SerializeMap for MapCapture {
+    type Ok = Value;
+    type Error = Error;
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Error> {
+        let key = match key.serialize(ValueSerializer)? {
+            Value::Str(key) => key,
+            other => fail_key(other)?,
+        };
+        self.pending_key = Some(key);
+        Ok(())
+    }
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        let key = self
+            .pending_key
+            .take()
+            .ok_or_else(|| Error::custom_str("serialize_value called before serialize_key"))?;
+        let value = value.serialize(ValueSerializer)?;
+
+        if let Some(pos) = self.fields.iter().position(|(k, _)| *k == key) {
+            let policy = crate::internal::CONFIGURATION.read().unwrap().duplicate_key_policy;
+            match policy {
+                DuplicateKeyPolicy::LastWins => self.fields[pos].1 = value,
+                DuplicateKeyPolicy::FirstWins => {}
+                DuplicateKeyPolicy::Error => {
+                    return Err(Error::custom_str(&format!(
+                        "Duplicate key {key:?} encountered while serializing a map or \
+                         #[serde(flatten)] struct (see serde_arrow::experimental::configure)"
+                    )))
+                }
+            }
+        } else {
+            self.fields.push((key, value));
+        }
+
+        Ok(())
+    }
+    fn end(self) -> Result<Value, Error> {
+        Ok(Value::Struct(self.fields))
+    }
+}
+
+fn fail_key(value: Value) -> Result<String, Error> {
+    Err(Error::custom_str(&format!(
+        "Only string-keyed maps are supported, found {value:?}"
+    )))
+}
+
+impl Error {
+    fn custom_str(msg: &str) -> Self {
+        Error(msg.to_owned().into())
+    }
+}
+
+struct StructCapture(Vec<(String, Value)>);
+
+impl SerializeStruct for StructCapture {
+    type Ok = Value;
+    type Error = Error;
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.0.push((key.to_owned(), value.serialize(ValueSerializer)?));
+        Ok(())
+    }
+    fn end(self) -> Result<Value, Error> {
+        Ok(Value::Struct(self.0))
+    }
+}
+impl SerializeStructVariant for StructCapture {
+    type Ok = Value;
+    type Error = Error;
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        SerializeStruct::serialize_field(self, key, value)
+    }
+    fn end(self) -> Result<Value, Error> {
+        SerializeStruct::end(self)
+    }
+}
+
+impl<'de> IntoDeserializer<'de, Error> for Value {
+    type Deserializer = Value;
+
+    fn into_deserializer(self) -> Value {
+        self
+    }
+}
+
+impl<'de> de::Deserializer<'de> for Value {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self {
+            Value::Null => visitor.visit_unit(),
+            Value::Bool(v) => visitor.visit_bool(v),
+            Value::I64(v) => visitor.visit_i64(v),
+            Value::U64(v) => visitor.visit_u64(v),
+            Value::F64(v) => visitor.visit_f64(v),
+            Value::Str(v) => visitor.visit_string(v),
+            Value::Bytes(v) => visitor.visit_byte_buf(v),
+            Value::Seq(values) => visitor.visit_seq(SeqDeserializer::new(values.into_iter())),
+            Value::Struct(fields) => visitor.visit_map(StructMapAccess {
+                fields: fields.into_iter(),
+                value: None,
+            }),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self {
+            Value::Null => visitor.visit_none(),
+            other => visitor.visit_some(other),
+        }
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 u8 u16 u32 u64 f32 f64 char str string bytes
+        byte_buf unit unit_struct newtype_struct seq tuple tuple_struct map
+        struct enum identifier ignored_any
+    }
+}
+
+struct StructMapAccess {
+    fields: std::vec::IntoIter<(String, Value)>,
+    value: Option<Value>,
+}
+
+impl<'de> de::MapAccess<'de> for StructMapAccess {
+    type Error = Error;
+
+    fn next_key_seed<K: de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Error> {
+        match self.fields.next() {
+            Some((key, value)) => {
+                self.value = Some(value);
+                seed.deserialize(key.into_deserializer()).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<S: de::DeserializeSeed<'de>>(&mut self, seed: S) -> Result<S::Value, Error> {
+        let value = self
+            .value
+            .take()
+            .ok_or_else(|| Error::custom_str("next_value_seed called before next_key_seed"))?;
+        seed.deserialize(value)
+    }
+}
@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 
-use arrow::array::Date64Array;
+use arrow::array::{Date64Array, Decimal128Array};
 use chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
 use serde::{Deserialize, Serialize};
 
@@ -60,6 +60,68 @@ fn item_multi_field_structure() -> Result<()> {
     Ok(())
 }
 
+/// Round-trip `records` through [Schema::set_data_type] and
+/// [crate::arrow::to_record_batch] / [crate::arrow::from_record_batch],
+/// asserting the result matches the input. Shared by the `dtype_*` /
+/// `item_*` tests below that only differ in the record type and the
+/// [DataType] under test.
+fn assert_roundtrip<T>(records: &[T], field: &str, data_type: DataType) -> Result<()>
+where
+    T: Serialize + for<'de> Deserialize<'de> + PartialEq + std::fmt::Debug,
+{
+    let mut schema = Schema::from_records(records)?;
+    schema.set_data_type(field, data_type)?;
+
+    let batch = crate::arrow::to_record_batch(records, &schema)?;
+    let round_tripped: Vec<T> = crate::arrow::from_record_batch(&batch, &schema)?;
+
+    assert_eq!(round_tripped.as_slice(), records);
+
+    Ok(())
+}
+
+/// Test that byte columns backed by `serde_bytes` are correctly handled
+#[test]
+fn item_bytes() -> Result<()> {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Example {
+        #[serde(with = "serde_bytes")]
+        payload: Vec<u8>,
+    }
+
+    let examples = [
+        Example {
+            payload: vec![0, 1, 2, 3],
+        },
+        Example {
+            payload: vec![255, 254, 253],
+        },
+    ];
+
+    assert_roundtrip(&examples, "payload", DataType::Bytes)
+}
+
+/// Test that large byte columns backed by `serde_bytes` are correctly handled
+#[test]
+fn item_large_bytes() -> Result<()> {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Example {
+        #[serde(with = "serde_bytes")]
+        payload: Vec<u8>,
+    }
+
+    let examples = [
+        Example {
+            payload: vec![0, 1, 2, 3],
+        },
+        Example {
+            payload: vec![255, 254, 253],
+        },
+    ];
+
+    assert_roundtrip(&examples, "payload", DataType::LargeBytes)
+}
+
 /// Test that maps are correctly handled
 #[test]
 fn item_maps() -> Result<()> {
@@ -78,6 +140,47 @@ fn item_maps() -> Result<()> {
     Ok(())
 }
 
+/// Test that schemas traced from heterogeneous batches can be merged
+#[test]
+fn schema_merge() -> Result<()> {
+    #[derive(Serialize)]
+    struct Batch1 {
+        a: i32,
+        b: i32,
+    }
+
+    #[derive(Serialize)]
+    struct Batch2 {
+        a: i32,
+        c: f32,
+    }
+
+    let mut schema = Schema::from_records(&[Batch1 { a: 1, b: 2 }])?;
+    schema.update_from_records(&[Batch2 { a: 1, c: 2.0 }])?;
+
+    assert_eq!(schema.fields(), &["a", "b", "c"]);
+    assert_eq!(schema.data_type("a"), Some(&DataType::I32));
+    assert!(!schema.is_nullable("a"));
+    assert!(schema.is_nullable("b"));
+    assert!(schema.is_nullable("c"));
+
+    Ok(())
+}
+
+/// Test that conflicting data types are rejected when merging schemas
+#[test]
+fn schema_merge_conflict() -> Result<()> {
+    let mut left = Schema::new();
+    left.add_field("a", Some(DataType::I32), Some(false));
+
+    let mut right = Schema::new();
+    right.add_field("a", Some(DataType::Str), Some(false));
+
+    assert!(left.merge(&right).is_err());
+
+    Ok(())
+}
+
 /// Test that also children with `#[serde(flatten)]` are correctly handled
 ///
 #[test]
@@ -198,6 +301,145 @@ fn dtype_date64_str() -> Result<()> {
     Ok(())
 }
 
+/// Test that dates in a custom strftime format are correctly handled
+#[test]
+fn dtype_date64_fmt() -> Result<()> {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Record {
+        val: String,
+    }
+
+    let records = [
+        Record {
+            val: String::from("2021-06-15 09:25:02"),
+        },
+        Record {
+            val: String::from("2021-06-16 10:30:00"),
+        },
+    ];
+
+    assert_roundtrip(
+        &records,
+        "val",
+        DataType::NaiveDateTimeFmt(String::from("%Y-%m-%d %H:%M:%S")),
+    )
+}
+
+/// Reject formats that do not fully determine a date and time
+#[test]
+fn dtype_date64_fmt_rejects_incomplete_format() -> Result<()> {
+    let mut schema = Schema::new();
+    schema.add_field("val", Some(DataType::Str), Some(false));
+
+    let res = schema.set_data_type("val", DataType::NaiveDateTimeFmt(String::from("%Y-%m-%d")));
+    assert!(res.is_err());
+
+    Ok(())
+}
+
+/// Test that decimal strings are correctly handled
+#[test]
+fn dtype_decimal128_str() -> Result<()> {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Record {
+        val: String,
+    }
+
+    let records = [
+        Record {
+            val: String::from("-12.340"),
+        },
+        Record {
+            val: String::from("0.001"),
+        },
+    ];
+
+    assert_roundtrip(
+        &records,
+        "val",
+        DataType::Decimal128 {
+            precision: 10,
+            scale: 3,
+        },
+    )
+}
+
+/// Test that an already-scaled integer is used as-is, not scaled again
+#[test]
+fn dtype_decimal128_int() -> Result<()> {
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Record {
+        val: i64,
+    }
+
+    let records = &[Record { val: -12_340 }, Record { val: 1 }][..];
+
+    let mut schema = Schema::from_records(records)?;
+    schema.set_data_type(
+        "val",
+        DataType::Decimal128 {
+            precision: 10,
+            scale: 3,
+        },
+    )?;
+
+    let batch = crate::arrow::to_record_batch(records, &schema)?;
+    let array = batch
+        .column(0)
+        .as_any()
+        .downcast_ref::<Decimal128Array>()
+        .expect("expected a Decimal128 column");
+
+    assert_eq!(array.value(0), -12_340);
+    assert_eq!(array.value(1), 1);
+
+    Ok(())
+}
+
+/// Reject values whose digit count overflows the configured precision
+#[test]
+fn dtype_decimal128_rejects_overflow() -> Result<()> {
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Record {
+        val: i64,
+    }
+
+    // precision 3 only allows values in (-1000, 1000)
+    let records = &[Record { val: 1_000 }][..];
+
+    let mut schema = Schema::from_records(records)?;
+    schema.set_data_type(
+        "val",
+        DataType::Decimal128 {
+            precision: 3,
+            scale: 0,
+        },
+    )?;
+
+    let res = crate::arrow::to_record_batch(records, &schema);
+    assert!(res.is_err());
+
+    Ok(())
+}
+
+/// Reject precision/scale combinations that cannot be represented
+#[test]
+fn dtype_decimal128_rejects_invalid_scale() -> Result<()> {
+    let mut schema = Schema::new();
+    schema.add_field("val", Some(DataType::Str), Some(false));
+
+    let res = schema.set_data_type(
+        "val",
+        DataType::Decimal128 {
+            precision: 5,
+            scale: 6,
+        },
+    );
+    assert!(res.is_err());
+
+    Ok(())
+}
+
 /// Test that dates in i64 milliseconds are correctly handled
 #[test]
 fn dtype_date64_int() -> Result<()> {
@@ -236,3 +478,36 @@ fn dtype_date64_int() -> Result<()> {
 
     Ok(())
 }
+
+/// Test that records written with [crate::arrow2::write_ipc] can be read
+/// back with [crate::arrow2::read_ipc]
+#[cfg(feature = "arrow2-io_ipc")]
+#[test]
+fn arrow2_ipc_roundtrip() -> Result<()> {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Record {
+        name: String,
+        value: i64,
+    }
+
+    let records = vec![
+        Record {
+            name: String::from("a"),
+            value: 1,
+        },
+        Record {
+            name: String::from("b"),
+            value: 2,
+        },
+    ];
+
+    let schema = Schema::from_records(&records)?;
+
+    let mut buf = Vec::new();
+    crate::arrow2::write_ipc(&records, &schema, &mut buf)?;
+
+    let round_tripped: Vec<Record> = crate::arrow2::read_ipc(std::io::Cursor::new(&buf))?;
+    assert_eq!(round_tripped, records);
+
+    Ok(())
+}